@@ -42,9 +42,11 @@
  * and executed using Cargo or rustc with standard Rust tooling.
  */
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::sync::Mutex;
 use std::error::Error;
+use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // Constants
@@ -58,6 +60,8 @@ enum AppError {
     UserNotFound(u32),
     InvalidEmail(String),
     RepositoryError(String),
+    AuthenticationFailed(UserId),
+    ConversionError(String),
 }
 
 impl fmt::Display for AppError {
@@ -66,6 +70,10 @@ impl fmt::Display for AppError {
             AppError::UserNotFound(id) => write!(f, "User with ID {} not found", id),
             AppError::InvalidEmail(email) => write!(f, "Invalid email format: {}", email),
             AppError::RepositoryError(msg) => write!(f, "Repository error: {}", msg),
+            AppError::AuthenticationFailed(id) => {
+                write!(f, "Authentication failed for user {}", id)
+            }
+            AppError::ConversionError(msg) => write!(f, "Conversion error: {}", msg),
         }
     }
 }
@@ -84,12 +92,26 @@ enum UserStatus {
     Pending,
 }
 
-#[derive(Debug, Clone)]
+// Ordered by severity so a `max_level` threshold can filter with `>=`.
+// Declaration order defines the derived ordering: Debug < Info < Warn < Error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum LogLevel {
+    Debug,
     Info,
     Warn,
     Error,
-    Debug,
+}
+
+impl LogLevel {
+    // Lowercase tag used in structured/JSON output.
+    fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
 }
 
 // Structs
@@ -113,8 +135,8 @@ struct User {
 
 impl User {
     fn new(id: UserId, name: String, email: String, age: Option<u8>) -> Result<Self> {
-        if !is_valid_email(&email) {
-            return Err(AppError::InvalidEmail(email));
+        if let Err(reason) = parse_email(&email) {
+            return Err(AppError::InvalidEmail(format!("{} ({})", email, reason)));
         }
 
         let timestamp = SystemTime::now()
@@ -164,11 +186,59 @@ trait Repository<T> {
     fn delete(&mut self, id: UserId) -> Result<()>;
 }
 
+// A single structured log entry. Carries an optional `fields` payload so
+// callers can attach context (e.g. `user_id`) without baking it into the
+// message string.
+#[derive(Debug, Clone)]
+struct Record {
+    timestamp: u64,
+    level: LogLevel,
+    message: String,
+    fields: HashMap<String, String>,
+}
+
+// A destination for log records. Sinks own their formatting, so a `Logger`
+// stays agnostic to whether output is human-readable, JSON, or captured.
+trait Sink {
+    fn emit(&self, record: &Record);
+}
+
 trait Logger {
-    fn log(&self, level: LogLevel, message: &str);
+    // Records below this level are dropped before reaching the sink.
+    fn max_level(&self) -> LogLevel;
+    // Deliver a record that has already passed the level filter.
+    fn emit(&self, record: Record);
+
+    // Core entry point: build a record with structured fields and emit it if it
+    // clears the threshold.
+    fn log_fields(&self, level: LogLevel, message: &str, fields: HashMap<String, String>) {
+        if level < self.max_level() {
+            return;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.emit(Record {
+            timestamp,
+            level,
+            message: message.to_string(),
+            fields,
+        });
+    }
+
+    fn log(&self, level: LogLevel, message: &str) {
+        self.log_fields(level, message, HashMap::new());
+    }
+    fn debug(&self, message: &str) {
+        self.log(LogLevel::Debug, message);
+    }
     fn info(&self, message: &str) {
         self.log(LogLevel::Info, message);
     }
+    fn warn(&self, message: &str) {
+        self.log(LogLevel::Warn, message);
+    }
     fn error(&self, message: &str) {
         self.log(LogLevel::Error, message);
     }
@@ -199,6 +269,307 @@ impl InMemoryUserRepository {
         self.next_id += 1;
         Ok(id)
     }
+
+    // Bulk-import users from untyped string rows (CSV/TSV/env) using a
+    // declarative schema. `headers` pairs each column with its target field
+    // name and a `Conversion`; every cell is converted per the schema, then the
+    // row's `name`/`email`/`age` fields are validated and inserted. Per-row
+    // failures are collected instead of aborting the batch, so one malformed
+    // line does not discard the rest. Returns the ids that were inserted and
+    // the `(row_index, error)` pairs that were rejected.
+    fn import_rows(
+        &mut self,
+        headers: &[(String, Conversion)],
+        rows: impl Iterator<Item = Vec<String>>,
+    ) -> (Vec<UserId>, Vec<(usize, AppError)>) {
+        let mut inserted = Vec::new();
+        let mut errors = Vec::new();
+
+        for (index, row) in rows.enumerate() {
+            match self.import_one(headers, &row) {
+                Ok(id) => inserted.push(id),
+                Err(e) => errors.push((index, e)),
+            }
+        }
+
+        (inserted, errors)
+    }
+
+    // Convert and insert a single row. Split out so `import_rows` can funnel
+    // every failure mode through one `Result`.
+    fn import_one(&mut self, headers: &[(String, Conversion)], row: &[String]) -> Result<UserId> {
+        if row.len() != headers.len() {
+            return Err(AppError::ConversionError(format!(
+                "expected {} columns, got {}",
+                headers.len(),
+                row.len()
+            )));
+        }
+
+        let mut fields: HashMap<&str, TypedValue> = HashMap::new();
+        for ((name, conversion), raw) in headers.iter().zip(row.iter()) {
+            fields.insert(name.as_str(), conversion.convert(raw)?);
+        }
+
+        let name = fields
+            .get("name")
+            .ok_or_else(|| AppError::ConversionError("missing column: name".to_string()))?
+            .clone()
+            .into_string()?;
+        let email = fields
+            .get("email")
+            .ok_or_else(|| AppError::ConversionError("missing column: email".to_string()))?
+            .clone()
+            .into_string()?;
+        let age = match fields.get("age") {
+            Some(value) => Some(value.clone().into_u8()?),
+            None => None,
+        };
+
+        self.create_user(name, email, age)
+    }
+}
+
+// Bulk-import conversion pipeline
+//
+// Rows coming from CSV/TSV/env are untyped strings. A `Conversion` describes
+// how one column should be parsed, and a schema (`&[(field_name, Conversion)]`)
+// drives ingestion declaratively. Conversions parse from spec strings via
+// `FromStr` so the schema itself can come from configuration.
+
+// A value after it has been typed according to its column's `Conversion`.
+#[derive(Debug, Clone, PartialEq)]
+enum TypedValue {
+    Bytes(Vec<u8>),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    // Epoch seconds, matching `User.created_at`.
+    Timestamp(u64),
+}
+
+impl TypedValue {
+    // Render back to a `String`, used for text columns declared as `Bytes`.
+    fn into_string(self) -> Result<String> {
+        match self {
+            TypedValue::Bytes(bytes) => String::from_utf8(bytes)
+                .map_err(|e| AppError::ConversionError(format!("invalid UTF-8: {}", e))),
+            other => Err(AppError::ConversionError(format!(
+                "expected text, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    // Narrow an integer column into the `u8` that `User.age` expects.
+    fn into_u8(self) -> Result<u8> {
+        match self {
+            TypedValue::Integer(n) => u8::try_from(n)
+                .map_err(|_| AppError::ConversionError(format!("age {} out of range", n))),
+            other => Err(AppError::ConversionError(format!(
+                "expected integer age, got {:?}",
+                other
+            ))),
+        }
+    }
+}
+
+// How a raw string column should be converted. `TimestampFmt`/`TimestampTZFmt`
+// carry a strptime-style pattern; the others are self-describing.
+#[derive(Debug, Clone, PartialEq)]
+enum Conversion {
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    TimestampFmt(String),
+    TimestampTZFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = AppError;
+
+    // Parse a schema spec: `"int"`, `"float"`, `"bool"`, `"bytes"`,
+    // `"timestamp"`, or a patterned form `"timestamp|%Y-%m-%d"` /
+    // `"timestamptz|%Y-%m-%dT%H:%M:%S%z"`.
+    fn from_str(spec: &str) -> std::result::Result<Self, Self::Err> {
+        let (kind, fmt) = match spec.split_once('|') {
+            Some((kind, fmt)) => (kind.trim(), Some(fmt.to_string())),
+            None => (spec.trim(), None),
+        };
+        let conversion = match kind {
+            "bytes" | "str" | "string" => Conversion::Bytes,
+            "int" | "integer" => Conversion::Integer,
+            "float" | "double" => Conversion::Float,
+            "bool" | "boolean" => Conversion::Boolean,
+            "timestamp" => match fmt {
+                Some(fmt) => Conversion::TimestampFmt(fmt),
+                None => Conversion::Timestamp,
+            },
+            "timestamptz" => {
+                let fmt = fmt.ok_or_else(|| {
+                    AppError::ConversionError("timestamptz requires a format".to_string())
+                })?;
+                Conversion::TimestampTZFmt(fmt)
+            }
+            other => {
+                return Err(AppError::ConversionError(format!(
+                    "unknown conversion: {}",
+                    other
+                )))
+            }
+        };
+        Ok(conversion)
+    }
+}
+
+impl Conversion {
+    // Convert one raw field to its typed value per this spec.
+    fn convert(&self, raw: &str) -> Result<TypedValue> {
+        let fail = |what: &str| AppError::ConversionError(format!("{}: {:?}", what, raw));
+        match self {
+            Conversion::Bytes => Ok(TypedValue::Bytes(raw.as_bytes().to_vec())),
+            Conversion::Integer => raw
+                .trim()
+                .parse()
+                .map(TypedValue::Integer)
+                .map_err(|_| fail("not an integer")),
+            Conversion::Float => raw
+                .trim()
+                .parse()
+                .map(TypedValue::Float)
+                .map_err(|_| fail("not a float")),
+            Conversion::Boolean => match raw.trim().to_ascii_lowercase().as_str() {
+                "true" | "t" | "yes" | "y" | "1" => Ok(TypedValue::Boolean(true)),
+                "false" | "f" | "no" | "n" | "0" => Ok(TypedValue::Boolean(false)),
+                _ => Err(fail("not a boolean")),
+            },
+            Conversion::Timestamp => raw
+                .trim()
+                .parse()
+                .map(TypedValue::Timestamp)
+                .map_err(|_| fail("not an epoch timestamp")),
+            Conversion::TimestampFmt(fmt) => {
+                parse_timestamp(raw.trim(), fmt, 0).map(TypedValue::Timestamp)
+            }
+            Conversion::TimestampTZFmt(fmt) => {
+                parse_timestamp(raw.trim(), fmt, 0).map(TypedValue::Timestamp)
+            }
+        }
+    }
+}
+
+// Minimal strptime covering the directives needed for user ingestion:
+// `%Y %m %d %H %M %S %z` plus literal characters. Returns UTC epoch seconds;
+// `default_offset` seeds the timezone offset when the pattern has no `%z`.
+// A dependency-free stand-in for what `chrono` would otherwise provide.
+fn parse_timestamp(input: &str, fmt: &str, default_offset: i64) -> Result<u64> {
+    let fail = |what: &str| AppError::ConversionError(format!("timestamp {}: {:?}", what, input));
+
+    let mut year: i64 = 1970;
+    let (mut month, mut day) = (1i64, 1i64);
+    let (mut hour, mut min, mut sec) = (0i64, 0i64, 0i64);
+    let mut offset = default_offset;
+
+    let bytes = input.as_bytes();
+    let mut pos = 0usize;
+
+    // Read one to `width` base-10 digits starting at `pos`.
+    let take_num = |bytes: &[u8], pos: &mut usize, width: usize| -> Option<i64> {
+        let start = *pos;
+        while *pos < bytes.len() && *pos - start < width && bytes[*pos].is_ascii_digit() {
+            *pos += 1;
+        }
+        if *pos == start {
+            return None;
+        }
+        std::str::from_utf8(&bytes[start..*pos]).ok()?.parse().ok()
+    };
+
+    let mut spec = fmt.chars().peekable();
+    while let Some(ch) = spec.next() {
+        if ch != '%' {
+            // Literal character must match the input exactly.
+            if pos >= bytes.len() || bytes[pos] != ch as u8 {
+                return Err(fail("literal mismatch"));
+            }
+            pos += 1;
+            continue;
+        }
+        match spec.next() {
+            Some('Y') => year = take_num(bytes, &mut pos, 4).ok_or_else(|| fail("year"))?,
+            Some('m') => month = take_num(bytes, &mut pos, 2).ok_or_else(|| fail("month"))?,
+            Some('d') => day = take_num(bytes, &mut pos, 2).ok_or_else(|| fail("day"))?,
+            Some('H') => hour = take_num(bytes, &mut pos, 2).ok_or_else(|| fail("hour"))?,
+            Some('M') => min = take_num(bytes, &mut pos, 2).ok_or_else(|| fail("minute"))?,
+            Some('S') => sec = take_num(bytes, &mut pos, 2).ok_or_else(|| fail("second"))?,
+            Some('z') => offset = parse_offset(bytes, &mut pos).ok_or_else(|| fail("offset"))?,
+            Some('%') => {
+                if pos >= bytes.len() || bytes[pos] != b'%' {
+                    return Err(fail("literal mismatch"));
+                }
+                pos += 1;
+            }
+            Some(other) => {
+                return Err(AppError::ConversionError(format!(
+                    "unsupported timestamp directive: %{}",
+                    other
+                )))
+            }
+            None => return Err(fail("dangling %")),
+        }
+    }
+
+    if pos != bytes.len() {
+        return Err(fail("trailing input"));
+    }
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(fail("out of range"));
+    }
+
+    let days = days_from_civil(year, month as u32, day as u32);
+    let secs = days * 86_400 + hour * 3_600 + min * 60 + sec - offset;
+    if secs < 0 {
+        return Err(fail("before epoch"));
+    }
+    Ok(secs as u64)
+}
+
+// Parse a `%z` offset (`Z`, `+HHMM`, `+HH:MM`) into seconds east of UTC.
+fn parse_offset(bytes: &[u8], pos: &mut usize) -> Option<i64> {
+    if *pos < bytes.len() && (bytes[*pos] == b'Z' || bytes[*pos] == b'z') {
+        *pos += 1;
+        return Some(0);
+    }
+    let sign = match bytes.get(*pos)? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    *pos += 1;
+    let read2 = |bytes: &[u8], pos: &mut usize| -> Option<i64> {
+        let s = bytes.get(*pos..*pos + 2)?;
+        *pos += 2;
+        std::str::from_utf8(s).ok()?.parse().ok()
+    };
+    let hours = read2(bytes, pos)?;
+    if *pos < bytes.len() && bytes[*pos] == b':' {
+        *pos += 1;
+    }
+    let mins = read2(bytes, pos)?;
+    Some(sign * (hours * 3_600 + mins * 60))
+}
+
+// Days from 1970-01-01 to the given civil date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = (y - era * 400) as i64;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
 }
 
 impl Repository<User> for InMemoryUserRepository {
@@ -221,27 +592,770 @@ impl Repository<User> for InMemoryUserRepository {
     }
 }
 
-struct SimpleLogger;
+// Persistence layer
+//
+// `InMemoryUserRepository` keeps everything in RAM, which is fine for the demo
+// but loses all state on exit. `DurableUserRepository` adds crash durability
+// using a Bayou-style log-and-checkpoint scheme: every mutation is appended to
+// an operation log, and the full state is snapshotted periodically. On startup
+// `sync()` loads the newest checkpoint and replays only the operations that are
+// newer than it, so recovery cost is bounded by `KEEP_STATE_EVERY` rather than
+// the entire history.
+
+// Snapshot the full state after this many logged operations.
+const KEEP_STATE_EVERY: u64 = 64;
+
+// Blob-store abstraction so the backend is agnostic to disk vs. remote object
+// store. Keys are opaque strings; `list` enumerates every key under a prefix.
+trait Storage {
+    fn blob_fetch(&self, key: &str) -> Result<Vec<u8>>;
+    fn blob_put(&mut self, key: &str, data: &[u8]) -> Result<()>;
+    // Remove a key. Deleting a key that does not exist is a no-op so callers can
+    // garbage-collect idempotently.
+    fn blob_delete(&mut self, key: &str) -> Result<()>;
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+}
+
+// Zero-dependency default backend, mirroring `InMemoryUserRepository`. Useful
+// for tests and single-process runs that still want the log/checkpoint code
+// path exercised without touching the filesystem.
+struct InMemoryStorage {
+    blobs: HashMap<String, Vec<u8>>,
+}
+
+impl InMemoryStorage {
+    fn new() -> Self {
+        Self {
+            blobs: HashMap::new(),
+        }
+    }
+}
+
+impl Storage for InMemoryStorage {
+    fn blob_fetch(&self, key: &str) -> Result<Vec<u8>> {
+        self.blobs
+            .get(key)
+            .cloned()
+            .ok_or_else(|| AppError::RepositoryError(format!("blob not found: {}", key)))
+    }
+
+    fn blob_put(&mut self, key: &str, data: &[u8]) -> Result<()> {
+        self.blobs.insert(key.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn blob_delete(&mut self, key: &str) -> Result<()> {
+        self.blobs.remove(key);
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .blobs
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+}
+
+// A single mutation in the append-only log. `seq` is the monotonic ordering key
+// so replay is deterministic regardless of the order `list` returns keys in.
+#[derive(Debug, Clone)]
+enum Mutation {
+    Save(User),
+    Delete(UserId),
+}
+
+// Blob key prefixes. Keys embed the zero-padded `seq` so lexical ordering of
+// the key equals numeric ordering of the sequence.
+const OP_PREFIX: &str = "op/";
+const CHECKPOINT_PREFIX: &str = "checkpoint/";
+
+fn op_key(seq: u64) -> String {
+    format!("{}{:020}", OP_PREFIX, seq)
+}
+
+fn checkpoint_key(seq: u64) -> String {
+    format!("{}{:020}", CHECKPOINT_PREFIX, seq)
+}
+
+fn seq_of_key(key: &str) -> Option<u64> {
+    key.rsplit('/').next().and_then(|s| s.parse().ok())
+}
+
+// Durable repository backed by any `Storage`. State lives in memory for reads;
+// writes go to the log first, then to the map, so a crash mid-write leaves a
+// recoverable log.
+struct DurableUserRepository<S: Storage> {
+    storage: S,
+    users: HashMap<UserId, User>,
+    next_id: UserId,
+    // Monotonic sequence assigned to the next logged operation.
+    next_seq: u64,
+    // Operations logged since the last checkpoint.
+    since_checkpoint: u64,
+    // Sequence of the checkpoint currently reflected in `users`, or `None` when
+    // no checkpoint has been loaded yet. Kept distinct from `seq 0` so the very
+    // first operation is never mistaken for already-checkpointed state.
+    checkpoint_seq: Option<u64>,
+}
+
+impl<S: Storage> DurableUserRepository<S> {
+    fn new(storage: S) -> Self {
+        Self {
+            storage,
+            users: HashMap::new(),
+            next_id: 1,
+            next_seq: 0,
+            since_checkpoint: 0,
+            checkpoint_seq: None,
+        }
+    }
+
+    // Rebuild in-memory state from storage: load the newest checkpoint, replay
+    // every operation logged after it, then garbage-collect stale checkpoints.
+    fn sync(&mut self) -> Result<()> {
+        self.users.clear();
+
+        let latest_checkpoint = self
+            .storage
+            .list(CHECKPOINT_PREFIX)?
+            .into_iter()
+            .filter_map(|k| seq_of_key(&k))
+            .max();
+
+        if let Some(seq) = latest_checkpoint {
+            let blob = self.storage.blob_fetch(&checkpoint_key(seq))?;
+            self.load_checkpoint(&blob)?;
+            self.checkpoint_seq = Some(seq);
+        }
+
+        let mut ops: Vec<u64> = self
+            .storage
+            .list(OP_PREFIX)?
+            .into_iter()
+            .filter_map(|k| seq_of_key(&k))
+            .filter(|&seq| self.checkpoint_seq.map_or(true, |cp| seq > cp))
+            .collect();
+        ops.sort_unstable();
+
+        for seq in &ops {
+            let blob = self.storage.blob_fetch(&op_key(*seq))?;
+            let mutation = decode_mutation(&blob)?;
+            self.apply(mutation);
+        }
+
+        self.next_seq = ops
+            .last()
+            .copied()
+            .or(self.checkpoint_seq)
+            .map_or(0, |seq| seq + 1);
+        self.since_checkpoint = ops.len() as u64;
+        self.collect_stale_checkpoints()?;
+        Ok(())
+    }
+
+    // Append one mutation to the log, apply it, and checkpoint if due. The log
+    // write happens before the map mutation so the durable record wins.
+    fn record(&mut self, mutation: Mutation) -> Result<()> {
+        let seq = self.next_seq;
+        self.storage.blob_put(&op_key(seq), &encode_mutation(seq, &mutation))?;
+        self.next_seq += 1;
+        self.apply(mutation);
+
+        self.since_checkpoint += 1;
+        if self.since_checkpoint >= KEEP_STATE_EVERY {
+            self.checkpoint(seq)?;
+        }
+        Ok(())
+    }
+
+    // Apply a mutation to the in-memory map. Pure state transition, no I/O, so
+    // it is shared by both live writes and log replay.
+    fn apply(&mut self, mutation: Mutation) {
+        match mutation {
+            Mutation::Save(user) => {
+                self.next_id = self.next_id.max(user.id + 1);
+                self.users.insert(user.id, user);
+            }
+            Mutation::Delete(id) => {
+                self.users.remove(&id);
+            }
+        }
+    }
+
+    fn create_user(&mut self, name: String, email: String, age: Option<u8>) -> Result<UserId> {
+        if self.users.len() >= MAX_USERS {
+            return Err(AppError::RepositoryError("Maximum users reached".to_string()));
+        }
+
+        let id = self.next_id;
+        let user = User::new(id, name, email, age)?;
+        self.record(Mutation::Save(user))?;
+        Ok(id)
+    }
+
+    // Snapshot the full state at `seq`, then drop checkpoints older than it.
+    fn checkpoint(&mut self, seq: u64) -> Result<()> {
+        let blob = self.encode_checkpoint();
+        self.storage.blob_put(&checkpoint_key(seq), &blob)?;
+        self.checkpoint_seq = Some(seq);
+        self.since_checkpoint = 0;
+        self.collect_stale_checkpoints()
+    }
+
+    // Remove every checkpoint older than the one currently loaded; replay only
+    // ever needs the newest, so the rest are pure garbage.
+    fn collect_stale_checkpoints(&mut self) -> Result<()> {
+        let stale: Vec<String> = self
+            .storage
+            .list(CHECKPOINT_PREFIX)?
+            .into_iter()
+            .filter(|k| match (seq_of_key(k), self.checkpoint_seq) {
+                (Some(s), Some(cp)) => s < cp,
+                _ => false,
+            })
+            .collect();
+        for key in stale {
+            self.storage.blob_delete(&key)?;
+        }
+        Ok(())
+    }
+
+    fn encode_checkpoint(&self) -> Vec<u8> {
+        let mut out = format!("{}\n", self.next_id);
+        for user in self.users.values() {
+            out.push_str(&encode_user(user));
+            out.push('\n');
+        }
+        out.into_bytes()
+    }
+
+    fn load_checkpoint(&mut self, blob: &[u8]) -> Result<()> {
+        let text = String::from_utf8(blob.to_vec())
+            .map_err(|e| AppError::RepositoryError(format!("corrupt checkpoint: {}", e)))?;
+        let mut lines = text.lines();
+        self.next_id = lines
+            .next()
+            .and_then(|l| l.parse().ok())
+            .ok_or_else(|| AppError::RepositoryError("missing checkpoint header".to_string()))?;
+        for line in lines.filter(|l| !l.is_empty()) {
+            let user = decode_user(line)?;
+            self.users.insert(user.id, user);
+        }
+        Ok(())
+    }
+}
+
+impl<S: Storage> Repository<User> for DurableUserRepository<S> {
+    fn save(&mut self, user: User) -> Result<()> {
+        self.record(Mutation::Save(user))
+    }
+
+    fn find_by_id(&self, id: UserId) -> Result<&User> {
+        self.users.get(&id).ok_or(AppError::UserNotFound(id))
+    }
+
+    fn find_all(&self) -> Vec<&User> {
+        self.users.values().collect()
+    }
+
+    fn delete(&mut self, id: UserId) -> Result<()> {
+        if !self.users.contains_key(&id) {
+            return Err(AppError::UserNotFound(id));
+        }
+        self.record(Mutation::Delete(id))
+    }
+}
+
+// Wire format. Records are single lines of `\x1f`-separated fields with `\` /
+// newline / separator escaped, so arbitrary user text round-trips intact.
+const FIELD_SEP: char = '\u{1f}';
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            FIELD_SEP => out.push_str("\\u"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.next() {
+                Some('\\') => out.push('\\'),
+                Some('n') => out.push('\n'),
+                Some('u') => out.push(FIELD_SEP),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+fn encode_user(user: &User) -> String {
+    let status = match user.status {
+        UserStatus::Active => "A",
+        UserStatus::Inactive => "I",
+        UserStatus::Pending => "P",
+    };
+    let age = user.age.map_or_else(|| "-".to_string(), |a| a.to_string());
+    [
+        user.id.to_string(),
+        escape(&user.name),
+        escape(&user.email),
+        age,
+        status.to_string(),
+        escape(&user.preferences.theme),
+        if user.preferences.notifications { "1" } else { "0" }.to_string(),
+        escape(&user.preferences.language),
+        user.created_at.to_string(),
+    ]
+    .join(&FIELD_SEP.to_string())
+}
+
+fn decode_user(line: &str) -> Result<User> {
+    let fields: Vec<&str> = line.split(FIELD_SEP).collect();
+    if fields.len() != 9 {
+        return Err(AppError::RepositoryError(format!(
+            "expected 9 user fields, got {}",
+            fields.len()
+        )));
+    }
+    let parse_err = |what: &str| AppError::RepositoryError(format!("bad user {}", what));
+    let status = match fields[4] {
+        "A" => UserStatus::Active,
+        "I" => UserStatus::Inactive,
+        "P" => UserStatus::Pending,
+        _ => return Err(parse_err("status")),
+    };
+    let age = match fields[3] {
+        "-" => None,
+        other => Some(other.parse().map_err(|_| parse_err("age"))?),
+    };
+    Ok(User {
+        id: fields[0].parse().map_err(|_| parse_err("id"))?,
+        name: unescape(fields[1]),
+        email: unescape(fields[2]),
+        age,
+        status,
+        preferences: UserPreferences {
+            theme: unescape(fields[5]),
+            notifications: fields[6] == "1",
+            language: unescape(fields[7]),
+        },
+        created_at: fields[8].parse().map_err(|_| parse_err("created_at"))?,
+    })
+}
+
+fn encode_mutation(seq: u64, mutation: &Mutation) -> Vec<u8> {
+    let body = match mutation {
+        Mutation::Save(user) => format!("S{}{}", FIELD_SEP, encode_user(user)),
+        Mutation::Delete(id) => format!("D{}{}", FIELD_SEP, id),
+    };
+    format!("{}{}{}", seq, FIELD_SEP, body).into_bytes()
+}
+
+fn decode_mutation(blob: &[u8]) -> Result<Mutation> {
+    let text = String::from_utf8(blob.to_vec())
+        .map_err(|e| AppError::RepositoryError(format!("corrupt operation: {}", e)))?;
+    // Strip the leading `seq\x1f`; ordering is carried by the blob key.
+    let body = text
+        .splitn(2, FIELD_SEP)
+        .nth(1)
+        .ok_or_else(|| AppError::RepositoryError("empty operation".to_string()))?;
+    let (tag, rest) = body
+        .split_once(FIELD_SEP)
+        .ok_or_else(|| AppError::RepositoryError("malformed operation".to_string()))?;
+    match tag {
+        "S" => Ok(Mutation::Save(decode_user(rest)?)),
+        "D" => Ok(Mutation::Delete(
+            rest.parse()
+                .map_err(|_| AppError::RepositoryError("bad delete id".to_string()))?,
+        )),
+        _ => Err(AppError::RepositoryError(format!("unknown op tag: {}", tag))),
+    }
+}
+
+// Human-readable sink: `[ts] Level: message k=v, ...` on stdout.
+struct StdoutSink;
+
+impl Sink for StdoutSink {
+    fn emit(&self, record: &Record) {
+        if record.fields.is_empty() {
+            println!(
+                "[{}] {:?}: {}",
+                record.timestamp, record.level, record.message
+            );
+        } else {
+            println!(
+                "[{}] {:?}: {} {}",
+                record.timestamp,
+                record.level,
+                record.message,
+                format_fields(&record.fields)
+            );
+        }
+    }
+}
+
+// JSON-lines sink: one object per line, suitable for log shippers.
+struct JsonLinesSink;
+
+impl Sink for JsonLinesSink {
+    fn emit(&self, record: &Record) {
+        println!("{}", record_to_json(record));
+    }
+}
+
+// Bounded in-memory sink for tests: keeps the most recent `capacity` records so
+// assertions can inspect what was logged without scraping stdout.
+struct RingSink {
+    records: Mutex<VecDeque<Record>>,
+    capacity: usize,
+}
+
+impl RingSink {
+    fn new(capacity: usize) -> Self {
+        Self {
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    // Snapshot of the buffered records, oldest first.
+    fn records(&self) -> Vec<Record> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+impl Sink for RingSink {
+    fn emit(&self, record: &Record) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut buf = self.records.lock().unwrap();
+        if buf.len() == self.capacity {
+            buf.pop_front();
+        }
+        buf.push_back(record.clone());
+    }
+}
+
+// Render structured fields as `k=v, k=v` with keys sorted for deterministic
+// output.
+fn format_fields(fields: &HashMap<String, String>) -> String {
+    let mut keys: Vec<&String> = fields.keys().collect();
+    keys.sort();
+    keys.iter()
+        .map(|k| format!("{}={}", k, fields[*k]))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Serialize a record to a single-line JSON object. Keys are emitted in a fixed
+// order, with `fields` nested and sorted, so lines are stable.
+fn record_to_json(record: &Record) -> String {
+    let mut out = String::from("{");
+    out.push_str(&format!("\"timestamp\":{}", record.timestamp));
+    out.push_str(&format!(",\"level\":\"{}\"", record.level.as_str()));
+    out.push_str(&format!(",\"message\":{}", json_string(&record.message)));
+    if !record.fields.is_empty() {
+        let mut keys: Vec<&String> = record.fields.keys().collect();
+        keys.sort();
+        let body = keys
+            .iter()
+            .map(|k| format!("{}:{}", json_string(k), json_string(&record.fields[*k])))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&format!(",\"fields\":{{{}}}", body));
+    }
+    out.push('}');
+    out
+}
+
+// Minimal JSON string escaping for the control characters and quotes that can
+// appear in messages and field values.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Human-readable logger and the default. Filters by `max_level` and writes to
+// stdout via `StdoutSink`.
+struct SimpleLogger {
+    max_level: LogLevel,
+    sink: StdoutSink,
+}
+
+impl SimpleLogger {
+    fn new() -> Self {
+        Self {
+            max_level: LogLevel::Info,
+            sink: StdoutSink,
+        }
+    }
+
+    // Raise or lower the threshold below which records are dropped.
+    fn with_level(mut self, level: LogLevel) -> Self {
+        self.max_level = level;
+        self
+    }
+}
+
+impl Default for SimpleLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Logger for SimpleLogger {
-    fn log(&self, level: LogLevel, message: &str) {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        println!("[{}] {:?}: {}", timestamp, level, message);
+    fn max_level(&self) -> LogLevel {
+        self.max_level
+    }
+
+    fn emit(&self, record: Record) {
+        self.sink.emit(&record);
+    }
+}
+
+// Structured logger over any `Sink`. Defaults to JSON-lines on stdout; swap in
+// a `RingSink` to capture records in tests.
+struct JsonLogger<S: Sink> {
+    max_level: LogLevel,
+    sink: S,
+}
+
+impl JsonLogger<JsonLinesSink> {
+    fn new() -> Self {
+        Self {
+            max_level: LogLevel::Info,
+            sink: JsonLinesSink,
+        }
+    }
+}
+
+impl<S: Sink> JsonLogger<S> {
+    // Build a structured logger over an explicit sink.
+    fn with_sink(max_level: LogLevel, sink: S) -> Self {
+        Self { max_level, sink }
+    }
+}
+
+impl<S: Sink> Logger for JsonLogger<S> {
+    fn max_level(&self) -> LogLevel {
+        self.max_level
+    }
+
+    fn emit(&self, record: Record) {
+        self.sink.emit(&record);
+    }
+}
+
+// Credential subsystem
+//
+// Secrets are kept out of the public `User` profile, mirroring the way Unix
+// splits `/etc/passwd` (world-readable profile data) from `/etc/shadow`
+// (root-only hashes). Code paths that only read profiles never gain access to
+// this map, so a profile leak does not leak password material.
+
+// Derived-hash and salt widths for the KDF below.
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+
+// Per-user secret record. Never embedded in `User`; lives in its own store.
+#[derive(Debug, Clone)]
+struct Credentials {
+    salt: [u8; SALT_LEN],
+    hash: [u8; HASH_LEN],
+    last_changed: u64,
+}
+
+// Policy applied when a password is *set*. Kept as a struct so callers can tune
+// it without touching service internals.
+#[derive(Debug, Clone)]
+struct PasswordPolicy {
+    min_length: usize,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self { min_length: 8 }
+    }
+}
+
+impl PasswordPolicy {
+    // A policy violation is a repository-level rejection, matching how other
+    // structural constraints (e.g. the user cap) surface.
+    fn check(&self, plaintext: &str) -> Result<()> {
+        if plaintext.chars().count() < self.min_length {
+            return Err(AppError::RepositoryError(format!(
+                "password must be at least {} characters",
+                self.min_length
+            )));
+        }
+        Ok(())
     }
 }
 
+// Cost parameter for the KDF's memory-filling pass. Larger values raise the
+// work (and memory) required per guess.
+const KDF_BLOCKS: usize = 1 << 12;
+
+// Memory-hard key derivation in the spirit of scrypt/argon2: fill a large
+// buffer deterministically from the password and salt, then make a pass in
+// which each step reads a pseudo-randomly chosen block of the buffer, so the
+// whole buffer must be held in memory to reproduce the result. This is a dependency-free
+// stand-in; a production build would pull in a vetted argon2 crate.
+fn derive_hash(plaintext: &[u8], salt: &[u8]) -> [u8; HASH_LEN] {
+    // FNV-1a style mixing primitive used to seed and stir the buffer.
+    fn mix(seed: u64, bytes: &[u8]) -> u64 {
+        let mut h = seed ^ 0xcbf2_9ce4_8422_2325;
+        for &b in bytes {
+            h ^= b as u64;
+            h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        h
+    }
+
+    let mut buffer = vec![0u64; KDF_BLOCKS];
+    let mut state = mix(mix(0, salt), plaintext);
+    for slot in buffer.iter_mut() {
+        state = mix(state, &state.to_le_bytes());
+        *slot = state;
+    }
+    // Memory-hard stirring: each step mixes in a data-dependent block.
+    for i in 0..KDF_BLOCKS {
+        let j = (buffer[i] as usize) % KDF_BLOCKS;
+        state = mix(state ^ buffer[j], &buffer[i].to_le_bytes());
+        buffer[i] = state;
+    }
+
+    let mut out = [0u8; HASH_LEN];
+    for (chunk, slot) in out.chunks_mut(8).zip(buffer.iter().rev()) {
+        chunk.copy_from_slice(&slot.to_le_bytes()[..chunk.len()]);
+    }
+    out
+}
+
+// Best-effort salt generation without a `rand` dependency: fold the high-res
+// clock through the KDF mixing so repeated calls in the same nanosecond still
+// diverge via the process-lifetime counter.
+fn generate_salt() -> [u8; SALT_LEN] {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let seed = derive_hash(&nanos.to_le_bytes(), &n.to_le_bytes());
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(&seed[..SALT_LEN]);
+    salt
+}
+
+// Constant-time equality over equal-length byte slices, so verification time
+// does not leak how many leading bytes matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
 // Service layer
 struct UserService<R: Repository<User>, L: Logger> {
     repository: R,
     logger: L,
+    // Shadow store: per-user secrets, isolated from the profile repository.
+    credentials: HashMap<UserId, Credentials>,
+    policy: PasswordPolicy,
 }
 
 impl<R: Repository<User>, L: Logger> UserService<R, L> {
     fn new(repository: R, logger: L) -> Self {
-        Self { repository, logger }
+        Self {
+            repository,
+            logger,
+            credentials: HashMap::new(),
+            policy: PasswordPolicy::default(),
+        }
+    }
+
+    // Override the default password policy.
+    fn with_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    // Set (or rotate) a user's password. Enforces the policy, derives a fresh
+    // salted hash, and stores only the hash — never the plaintext.
+    fn set_password(&mut self, id: UserId, plaintext: &str) -> Result<()> {
+        // Reject unknown users up front so we never store orphan credentials.
+        self.repository.find_by_id(id)?;
+        self.policy.check(plaintext)?;
+
+        let salt = generate_salt();
+        let hash = derive_hash(plaintext.as_bytes(), &salt);
+        let last_changed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.credentials.insert(
+            id,
+            Credentials {
+                salt,
+                hash,
+                last_changed,
+            },
+        );
+        let mut fields = HashMap::new();
+        fields.insert("user_id".to_string(), id.to_string());
+        self.logger.log_fields(LogLevel::Info, "Password set", fields);
+        Ok(())
+    }
+
+    // Verify a plaintext against the stored hash. Returns `Ok(false)` on a
+    // mismatch and `Err(AuthenticationFailed)` when the user has no stored
+    // credentials.
+    fn verify_password(&self, id: UserId, plaintext: &str) -> Result<bool> {
+        let creds = self
+            .credentials
+            .get(&id)
+            .ok_or(AppError::AuthenticationFailed(id))?;
+        let candidate = derive_hash(plaintext.as_bytes(), &creds.salt);
+        Ok(constant_time_eq(&candidate, &creds.hash))
     }
 
     fn get_user_stats(&self) -> HashMap<String, u32> {
@@ -263,9 +1377,208 @@ impl<R: Repository<User>, L: Logger> UserService<R, L> {
     }
 }
 
-// Utility functions
-fn is_valid_email(email: &str) -> bool {
-    email.contains('@') && email.contains('.')
+// Email parsing
+//
+// The old `is_valid_email` just checked for `'@'` and `'.'`, which happily
+// accepts garbage like `@.`. This replaces that heuristic with an explicit
+// RFC-5322-flavored grammar — a small lexer/validator over the local part and
+// domain — so the rules are auditable and each rejection says *where* it failed.
+
+// The normalized `{local, domain}` split of a valid address.
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedEmail {
+    local: String,
+    domain: String,
+}
+
+// Precise reason an address was rejected, including the offending character or
+// length where relevant.
+#[derive(Debug, Clone, PartialEq)]
+enum EmailError {
+    Empty,
+    EmptyLocal,
+    MissingAt,
+    MultipleAt,
+    EmptyDomain,
+    EmptyAtom,
+    InvalidLocalChar(char),
+    UnterminatedQuote,
+    LabelEmpty,
+    LabelTooLong(usize),
+    LabelLeadingHyphen,
+    LabelTrailingHyphen,
+    InvalidDomainChar(char),
+    LocalTooLong(usize),
+    DomainTooLong(usize),
+    TooLong(usize),
+}
+
+impl fmt::Display for EmailError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmailError::Empty => write!(f, "address is empty"),
+            EmailError::EmptyLocal => write!(f, "empty local part"),
+            EmailError::MissingAt => write!(f, "missing '@' separator"),
+            EmailError::MultipleAt => write!(f, "more than one '@'"),
+            EmailError::EmptyDomain => write!(f, "empty domain"),
+            EmailError::EmptyAtom => write!(f, "empty atom in local part"),
+            EmailError::InvalidLocalChar(c) => write!(f, "invalid character {:?} in local part", c),
+            EmailError::UnterminatedQuote => write!(f, "unterminated quoted local part"),
+            EmailError::LabelEmpty => write!(f, "empty domain label"),
+            EmailError::LabelTooLong(n) => write!(f, "domain label too long ({} chars)", n),
+            EmailError::LabelLeadingHyphen => write!(f, "domain label starts with '-'"),
+            EmailError::LabelTrailingHyphen => write!(f, "domain label ends with '-'"),
+            EmailError::InvalidDomainChar(c) => write!(f, "invalid character {:?} in domain", c),
+            EmailError::LocalTooLong(n) => write!(f, "local part too long ({} chars)", n),
+            EmailError::DomainTooLong(n) => write!(f, "domain too long ({} chars)", n),
+            EmailError::TooLong(n) => write!(f, "address too long ({} chars)", n),
+        }
+    }
+}
+
+// Overall length bounds (RFC 5321 §4.5.3.1).
+const MAX_EMAIL_LEN: usize = 254;
+const MAX_LOCAL_LEN: usize = 64;
+const MAX_DOMAIN_LEN: usize = 253;
+const MAX_LABEL_LEN: usize = 63;
+
+// Parse and validate an address, returning its normalized `{local, domain}`
+// split or a precise `EmailError`.
+fn parse_email(input: &str) -> std::result::Result<ParsedEmail, EmailError> {
+    if input.is_empty() {
+        return Err(EmailError::Empty);
+    }
+    if input.len() > MAX_EMAIL_LEN {
+        return Err(EmailError::TooLong(input.len()));
+    }
+
+    let (local, domain) = split_local_domain(input)?;
+    validate_local(local)?;
+    validate_domain(domain)?;
+
+    Ok(ParsedEmail {
+        local: local.to_string(),
+        domain: domain.to_string(),
+    })
+}
+
+// Split the address at the single unquoted `@`. A `@` inside a quoted local
+// part belongs to the local part, not the separator; `\` escapes a character
+// only inside quotes.
+fn split_local_domain(input: &str) -> std::result::Result<(&str, &str), EmailError> {
+    let mut in_quote = false;
+    let mut escaped = false;
+    let mut separator = None;
+
+    for (idx, ch) in input.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if in_quote => escaped = true,
+            '"' => in_quote = !in_quote,
+            '@' if !in_quote => {
+                separator = Some(idx);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let at = separator.ok_or(EmailError::MissingAt)?;
+    let domain = &input[at + 1..];
+    if domain.contains('@') {
+        return Err(EmailError::MultipleAt);
+    }
+    Ok((&input[..at], domain))
+}
+
+// Characters permitted in an unquoted local-part atom (RFC 5322 `atext`).
+fn is_atext(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || "!#$%&'*+/=?^_`{|}~-".contains(ch)
+}
+
+// Validate the local part: either a quoted string or dot-separated atoms with
+// no empty atom (which rules out leading, trailing, and doubled dots).
+fn validate_local(local: &str) -> std::result::Result<(), EmailError> {
+    if local.is_empty() {
+        return Err(EmailError::EmptyLocal);
+    }
+    if local.len() > MAX_LOCAL_LEN {
+        return Err(EmailError::LocalTooLong(local.len()));
+    }
+
+    if local.starts_with('"') {
+        return validate_quoted_local(local);
+    }
+
+    for atom in local.split('.') {
+        if atom.is_empty() {
+            return Err(EmailError::EmptyAtom);
+        }
+        if let Some(bad) = atom.chars().find(|&c| !is_atext(c)) {
+            return Err(EmailError::InvalidLocalChar(bad));
+        }
+    }
+    Ok(())
+}
+
+// Validate a quoted local part: it must open and close with `"`, and any `"` or
+// `\` inside must be backslash-escaped.
+fn validate_quoted_local(local: &str) -> std::result::Result<(), EmailError> {
+    let inner = local
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or(EmailError::UnterminatedQuote)?;
+    if local.len() == 1 {
+        return Err(EmailError::UnterminatedQuote);
+    }
+
+    let mut escaped = false;
+    for ch in inner.chars() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == '"' {
+            return Err(EmailError::UnterminatedQuote);
+        }
+    }
+    if escaped {
+        return Err(EmailError::UnterminatedQuote);
+    }
+    Ok(())
+}
+
+// Validate the domain: dot-separated labels, each 1-63 chars of letters,
+// digits, or hyphens, with no leading or trailing hyphen.
+fn validate_domain(domain: &str) -> std::result::Result<(), EmailError> {
+    if domain.is_empty() {
+        return Err(EmailError::EmptyDomain);
+    }
+    if domain.len() > MAX_DOMAIN_LEN {
+        return Err(EmailError::DomainTooLong(domain.len()));
+    }
+
+    for label in domain.split('.') {
+        if label.is_empty() {
+            return Err(EmailError::LabelEmpty);
+        }
+        if label.len() > MAX_LABEL_LEN {
+            return Err(EmailError::LabelTooLong(label.len()));
+        }
+        if label.starts_with('-') {
+            return Err(EmailError::LabelLeadingHyphen);
+        }
+        if label.ends_with('-') {
+            return Err(EmailError::LabelTrailingHyphen);
+        }
+        if let Some(bad) = label.chars().find(|&c| !(c.is_ascii_alphanumeric() || c == '-')) {
+            return Err(EmailError::InvalidDomainChar(bad));
+        }
+    }
+    Ok(())
 }
 
 fn fibonacci(n: usize) -> Vec<u64> {
@@ -298,7 +1611,7 @@ fn main() -> Result<()> {
     println!("{}", "=".repeat(30));
 
     // Initialize dependencies
-    let logger = SimpleLogger;
+    let logger = SimpleLogger::new();
     let mut repository = InMemoryUserRepository::new();
     
     logger.info("Application started");